@@ -5,6 +5,195 @@ use std::{collections::HashMap, fmt::Formatter};
 
 const END_OF_TAG: &[u8] = &[b'<', b'/'];
 const SELF_CLOSING: &[u8] = &[b'/', b'>'];
+const COMMENT_START: &[u8] = b"<!--";
+const COMMENT_END: &[u8] = b"-->";
+const DECLARATION_START: &[u8] = b"<!";
+
+/// Elements whose contents must be read verbatim (no child parsing) up to the
+/// matching closing tag, since their bodies are not actually HTML (e.g. `<` in a
+/// `<script>` body is just JS and must not be interpreted as a tag).
+const RAW_TEXT_ELEMENTS: &[&[u8]] = &[b"script", b"style", b"textarea"];
+
+fn is_raw_text_node(name: &[u8]) -> bool {
+    RAW_TEXT_ELEMENTS.iter().any(|el| el.eq_ignore_ascii_case(name))
+}
+
+/// For a given currently-open tag, the set of start tags that implicitly close it, as
+/// real-world HTML frequently omits end tags (e.g. `<li>` items without `</li>`, or a
+/// `<p>` that is closed by a following block-level element rather than `</p>`).
+const IMPLICIT_END_TAGS: &[(&[u8], &[&[u8]])] = &[
+    (
+        b"p",
+        &[
+            b"address", b"article", b"aside", b"blockquote", b"details", b"div", b"dl",
+            b"fieldset", b"figcaption", b"figure", b"footer", b"form", b"h1", b"h2", b"h3",
+            b"h4", b"h5", b"h6", b"header", b"hr", b"main", b"menu", b"nav", b"ol", b"p",
+            b"pre", b"section", b"table", b"ul",
+        ],
+    ),
+    (b"li", &[b"li"]),
+    (b"dt", &[b"dt", b"dd"]),
+    (b"dd", &[b"dt", b"dd"]),
+    (b"option", &[b"option", b"optgroup"]),
+    (b"tr", &[b"tr"]),
+    (b"td", &[b"td", b"th", b"tr"]),
+    (b"th", &[b"td", b"th", b"tr"]),
+];
+
+/// Whether an open element named `open` should be implicitly closed by a new start tag
+/// named `incoming`.
+fn auto_closes(open: &[u8], incoming: &[u8]) -> bool {
+    IMPLICIT_END_TAGS
+        .iter()
+        .find(|(tag, _)| tag.eq_ignore_ascii_case(open))
+        .map_or(false, |(_, closers)| {
+            closers.iter().any(|c| c.eq_ignore_ascii_case(incoming))
+        })
+}
+
+/// What kind of markup construct starts at the current position, assuming the stream
+/// is positioned at a `<`. Shared by the strict (`parse_single`) and two-pass
+/// (`tokenize`) parsing paths so the lookahead only needs to be maintained once.
+enum MarkupKind {
+    Comment,
+    Doctype,
+    EndTag,
+    StartTag,
+}
+
+/// Options that control parsing behavior. This is the single, crate-wide options type
+/// threaded through from the top-level `parse`/`parse_owned` entry points down into
+/// [`Parser::new`] — there is no separate, parser-local options type.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParserOptions {
+    strict: bool,
+}
+
+impl ParserOptions {
+    /// Disables HTML5-style implicit tag closing and instead aborts the entire parse as
+    /// soon as a closing tag doesn't match the currently open element, which was the
+    /// original (and much more fragile) behavior of this parser.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+/// A single token produced by the first (tokenizing) pass of the HTML5-style parser.
+/// The second pass turns a flat stream of these into a [`Tree`] by maintaining a stack
+/// of currently open elements.
+#[derive(Debug)]
+enum Token<'a> {
+    StartTag {
+        name: &'a [u8],
+        attributes: HashMap<&'a [u8], &'a [u8]>,
+        self_closing: bool,
+    },
+    EndTag {
+        name: &'a [u8],
+    },
+    Text(&'a [u8]),
+    Comment(&'a [u8]),
+    Doctype(&'a [u8]),
+}
+
+/// An element that has been opened (its start tag was seen) but not yet closed, while
+/// building the tree from a token stream.
+struct OpenElement<'a> {
+    name: &'a [u8],
+    attributes: HashMap<&'a [u8], &'a [u8]>,
+    children: Vec<Node<'a>>,
+}
+
+fn push_child<'a>(stack: &mut Vec<OpenElement<'a>>, root: &mut Tree<'a>, node: Node<'a>) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => root.push(node),
+    }
+}
+
+fn close_element<'a>(stack: &mut Vec<OpenElement<'a>>, root: &mut Tree<'a>) {
+    if let Some(el) = stack.pop() {
+        let tag = HTMLTag::new(el.name, el.attributes, el.children);
+        push_child(stack, root, Node::Tag(tag));
+    }
+}
+
+/// Closes (and attaches to their parent) any currently open elements that `incoming`
+/// implicitly closes, per [`auto_closes`].
+///
+/// Walks the whole stack, not just the top: an intervening element that `incoming`
+/// doesn't close (e.g. `<b>` between an open `<p>` and a new `<div>`) must not stop the
+/// scan, or the `<p>` would never see its implicit close. Once the lowest matching
+/// element is found, everything above it is closed too, since it's nested inside.
+fn close_implied_elements<'a>(stack: &mut Vec<OpenElement<'a>>, root: &mut Tree<'a>, incoming: &[u8]) {
+    if let Some(pos) = stack.iter().position(|el| auto_closes(el.name, incoming)) {
+        while stack.len() > pos {
+            close_element(stack, root);
+        }
+    }
+}
+
+/// Builds a [`Tree`] from a flat token stream using a stack of open elements: a start
+/// tag pushes onto the stack (after auto-closing any elements `incoming` implies the
+/// end of), an end tag pops down to the nearest matching open element (implicitly
+/// closing anything in between), and a stray end tag with no matching open element is
+/// silently dropped. Anything still open once the tokens run out is closed at the root.
+fn build_tree(tokens: Vec<Token<'_>>) -> Tree<'_> {
+    let mut root = Vec::new();
+    let mut stack: Vec<OpenElement<'_>> = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Text(text) => push_child(&mut stack, &mut root, Node::Raw(text)),
+            Token::Comment(content) => push_child(&mut stack, &mut root, Node::Comment(content)),
+            Token::Doctype(content) => push_child(&mut stack, &mut root, Node::Doctype(content)),
+            Token::StartTag {
+                name,
+                attributes,
+                self_closing,
+            } => {
+                close_implied_elements(&mut stack, &mut root, name);
+
+                if self_closing || is_void_element(name) {
+                    let tag = HTMLTag::new(name, attributes, Vec::new());
+                    push_child(&mut stack, &mut root, Node::Tag(tag));
+                } else {
+                    stack.push(OpenElement {
+                        name,
+                        attributes,
+                        children: Vec::new(),
+                    });
+                }
+            }
+            Token::EndTag { name } => {
+                if let Some(pos) = stack.iter().rposition(|el| el.name.eq_ignore_ascii_case(name)) {
+                    while stack.len() > pos {
+                        close_element(&mut stack, &mut root);
+                    }
+                }
+                // else: no matching open element - drop the stray end tag
+            }
+        }
+    }
+
+    while !stack.is_empty() {
+        close_element(&mut stack, &mut root);
+    }
+
+    root
+}
+
+/// Elements that never have children or a closing tag, and are instead serialized with
+/// self-closing syntax (e.g. `<br />`).
+const VOID_ELEMENTS: &[&[u8]] = &[
+    b"area", b"base", b"br", b"col", b"embed", b"hr", b"img", b"input", b"link", b"meta",
+    b"param", b"source", b"track", b"wbr",
+];
+
+fn is_void_element(name: &[u8]) -> bool {
+    VOID_ELEMENTS.iter().any(|el| el.eq_ignore_ascii_case(name))
+}
 
 pub struct HTMLTag<'a> {
     _name: &'a [u8],
@@ -24,6 +213,36 @@ impl<'a> Debug for HTMLTag<'a> {
     }
 }
 
+/// A buffer that serialized HTML can be written into. Implemented for both [`String`]
+/// and [`Vec<u8>`] so [`HTMLTag::inner_html`]/[`HTMLTag::outer_html`] can allocate
+/// directly into whichever representation the caller already works in, instead of
+/// forcing a `String` and a second copy to get bytes.
+pub trait HtmlSink {
+    fn write_str(&mut self, s: &str);
+    fn write_char(&mut self, c: char);
+}
+
+impl HtmlSink for String {
+    fn write_str(&mut self, s: &str) {
+        String::push_str(self, s);
+    }
+
+    fn write_char(&mut self, c: char) {
+        String::push(self, c);
+    }
+}
+
+impl HtmlSink for Vec<u8> {
+    fn write_str(&mut self, s: &str) {
+        self.extend_from_slice(s.as_bytes());
+    }
+
+    fn write_char(&mut self, c: char) {
+        let mut buf = [0u8; 4];
+        self.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    }
+}
+
 impl<'a> HTMLTag<'a> {
     pub fn new(name: &'a [u8], attr: HashMap<&'a [u8], &'a [u8]>, children: Vec<Node<'a>>) -> Self {
         Self {
@@ -33,12 +252,102 @@ impl<'a> HTMLTag<'a> {
             _flags: 0,
         }
     }
+
+    /// Serializes this tag's children (but not the opening/closing tag of `self`) into
+    /// `output`, reconstructing valid HTML.
+    pub fn inner_html<W: HtmlSink>(&self, output: &mut W) {
+        for child in &self._children {
+            child.outer_html(output);
+        }
+    }
+
+    /// Serializes this tag, including its name, attributes and (recursively rendered)
+    /// children, into `output`.
+    pub fn outer_html<W: HtmlSink>(&self, output: &mut W) {
+        output.write_char('<');
+        output.write_str(&String::from_utf8_lossy(self._name));
+
+        for (name, value) in &self._attributes {
+            output.write_char(' ');
+            output.write_str(&String::from_utf8_lossy(name));
+
+            // Valueless attributes (e.g. `<input disabled>`) are emitted with no `=`
+            if !value.is_empty() {
+                let value = String::from_utf8_lossy(value);
+                let quote = if value.contains('"') { '\'' } else { '"' };
+                // Escape any occurrence of the chosen quote character still left in the
+                // value (e.g. a value containing both `"` and `'`), so it can't
+                // prematurely terminate the attribute.
+                let escaped = value.replace(
+                    quote,
+                    if quote == '"' { "&quot;" } else { "&#39;" },
+                );
+                output.write_char('=');
+                output.write_char(quote);
+                output.write_str(&escaped);
+                output.write_char(quote);
+            }
+        }
+
+        if is_void_element(self._name) {
+            output.write_str(" />");
+            return;
+        }
+
+        output.write_char('>');
+        self.inner_html(output);
+        output.write_str("</");
+        output.write_str(&String::from_utf8_lossy(self._name));
+        output.write_char('>');
+    }
 }
 
 #[derive(Debug)]
 pub enum Node<'a> {
     Tag(HTMLTag<'a>),
     Raw(&'a [u8]),
+    /// A `<!-- ... -->` comment. The contents exclude the `<!--`/`-->` markers.
+    Comment(&'a [u8]),
+    /// A `<!...>` declaration, such as `<!DOCTYPE html>`. The contents exclude the
+    /// leading `<!` and trailing `>`.
+    Doctype(&'a [u8]),
+}
+
+impl<'a> Node<'a> {
+    /// Serializes this node into `output`: a tag is rendered via
+    /// [`HTMLTag::outer_html`], raw text, comments and doctypes are written out
+    /// verbatim with their surrounding markers restored.
+    pub fn outer_html<W: HtmlSink>(&self, output: &mut W) {
+        match self {
+            Node::Tag(tag) => tag.outer_html(output),
+            Node::Raw(raw) => output.write_str(&String::from_utf8_lossy(raw)),
+            Node::Comment(content) => {
+                output.write_str("<!--");
+                output.write_str(&String::from_utf8_lossy(content));
+                output.write_str("-->");
+            }
+            Node::Doctype(content) => {
+                output.write_str("<!");
+                output.write_str(&String::from_utf8_lossy(content));
+                output.write_char('>');
+            }
+        }
+    }
+}
+
+/// Whether `tree`'s doctype (if any) is the HTML5 one (`<!DOCTYPE html>`). A doctype is
+/// now its own [`Node::Doctype`] node rather than being folded into [`Node::Raw`], so
+/// this looks for that node specifically instead of sniffing raw text. `pub(crate)`
+/// because the DOM layer's public `version()` API is what exposes this to callers; this
+/// is just the plumbing that reads the doctype node.
+pub(crate) fn doctype_is_html5(tree: &Tree<'_>) -> Option<bool> {
+    tree.iter().find_map(|node| match node {
+        Node::Doctype(content) => {
+            let content = String::from_utf8_lossy(content);
+            Some(content.trim().eq_ignore_ascii_case("DOCTYPE html"))
+        }
+        _ => None,
+    })
 }
 
 pub type Tree<'a> = Vec<Node<'a>>;
@@ -46,12 +355,14 @@ pub type Tree<'a> = Vec<Node<'a>>;
 #[derive(Debug)]
 pub struct Parser<'a> {
     stream: Stream<'a, u8>,
+    options: ParserOptions,
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(input: &str) -> Parser {
+    pub fn new(input: &str, options: ParserOptions) -> Parser {
         Parser {
             stream: Stream::new(input.as_bytes()),
+            options,
         }
     }
 
@@ -143,6 +454,116 @@ impl<'a> Parser<'a> {
         attr
     }
 
+    /// Scans verbatim until the closing tag for `name` is found (e.g. `</script>`), and
+    /// consumes it. The comparison is ASCII case-insensitive and only ends the raw text
+    /// when the closing sequence is immediately followed by whitespace, `>` or the end of
+    /// the input, so that
+    /// e.g. `</scripts>` inside a `<script>` body does not prematurely close it.
+    fn read_raw_text(&mut self, name: &'a [u8]) -> &'a [u8] {
+        let start = self.stream.idx;
+
+        loop {
+            let rest = self.stream.slice(self.stream.idx, usize::MAX);
+
+            match util::find_slice_fast(rest, END_OF_TAG) {
+                Some(offset) => {
+                    self.stream.idx += offset;
+
+                    if self.is_raw_text_close(name) {
+                        break;
+                    }
+
+                    self.stream.idx += 1;
+                }
+                None => {
+                    self.stream.idx += rest.len();
+                    break;
+                }
+            }
+        }
+
+        let raw = self.stream.slice_unchecked(start, self.stream.idx);
+
+        if !self.stream.is_eof() {
+            self.stream.idx += END_OF_TAG.len();
+            self.read_ident();
+            self.skip_whitespaces();
+            self.stream.expect_and_skip(b'>');
+        }
+
+        raw
+    }
+
+    /// Checks, without consuming anything, whether the stream is currently positioned at a
+    /// closing tag for `name`.
+    fn is_raw_text_close(&self, name: &[u8]) -> bool {
+        let idx = self.stream.idx;
+        let tag_len = END_OF_TAG.len() + name.len();
+
+        let slice = self.stream.slice(idx, idx + tag_len);
+
+        if slice.len() != tag_len || !slice[..END_OF_TAG.len()].eq(END_OF_TAG) {
+            return false;
+        }
+
+        if !slice[END_OF_TAG.len()..].eq_ignore_ascii_case(name) {
+            return false;
+        }
+
+        match self.stream.slice(idx + tag_len, idx + tag_len + 1).first() {
+            Some(b'>' | b' ' | b'\t' | b'\n' | b'\r') | None => true,
+            _ => false,
+        }
+    }
+
+    /// Determines what kind of markup construct starts at the current position,
+    /// assuming the stream is positioned at `<`.
+    fn classify_markup(&self) -> MarkupKind {
+        let idx = self.stream.idx;
+
+        if self.stream.slice(idx, idx + COMMENT_START.len()).eq(COMMENT_START) {
+            MarkupKind::Comment
+        } else if self.stream.slice(idx, idx + DECLARATION_START.len()).eq(DECLARATION_START) {
+            MarkupKind::Doctype
+        } else if self.stream.slice(idx, idx + END_OF_TAG.len()).eq(END_OF_TAG) {
+            MarkupKind::EndTag
+        } else {
+            MarkupKind::StartTag
+        }
+    }
+
+    /// Reads a `<!-- ... -->` comment, assuming the stream is currently positioned at
+    /// the leading `<!--`. Consumes up to and including the terminating `-->`, or to
+    /// EOF if it is never found. Returns the comment's contents, excluding the markers.
+    fn parse_comment(&mut self) -> &'a [u8] {
+        self.stream.idx += COMMENT_START.len();
+        let start = self.stream.idx;
+
+        let rest = self.stream.slice(self.stream.idx, usize::MAX);
+
+        match util::find_slice_fast(rest, COMMENT_END) {
+            Some(offset) => {
+                let content = self.stream.slice_unchecked(start, start + offset);
+                self.stream.idx = start + offset + COMMENT_END.len();
+                content
+            }
+            None => {
+                self.stream.idx += rest.len();
+                self.stream.slice_unchecked(start, self.stream.idx)
+            }
+        }
+    }
+
+    /// Reads a `<!...>` declaration (e.g. `<!DOCTYPE html>`), assuming the stream is
+    /// currently positioned at the leading `<!`. Returns its contents, excluding the
+    /// leading `<!` and trailing `>`.
+    fn parse_doctype(&mut self) -> &'a [u8] {
+        self.stream.idx += DECLARATION_START.len();
+        let content = self.read_to(&[b'>']);
+        self.stream.expect_and_skip(b'>');
+        content
+    }
+
     fn parse_tag(&mut self, skip_current: bool) -> Option<HTMLTag<'a>> {
         if skip_current {
             self.stream.next()?;
@@ -172,6 +593,16 @@ impl<'a> Parser<'a> {
 
         self.stream.expect_and_skip(b'>')?;
 
+        if is_raw_text_node(name) {
+            let raw = self.read_raw_text(name);
+
+            if !raw.is_empty() {
+                children.push(Node::Raw(raw));
+            }
+
+            return Some(HTMLTag::new(name, attr, children));
+        }
+
         while !self.stream.is_eof() {
             self.skip_whitespaces();
 
@@ -190,7 +621,6 @@ impl<'a> Parser<'a> {
                 break;
             }
 
-            // TODO: "partial" JS parser is needed to deal with script tags
             let node = self.parse_single()?;
 
             children.push(node);
@@ -206,22 +636,232 @@ impl<'a> Parser<'a> {
 
         let ch = self.stream.current_cpy()?;
 
-        match ch {
-            // TODO: if parse_tag fails (None case), we should probably just interpret it
-            // as raw text...
-            b'<' => self.parse_tag(true).and_then(|x| Some(Node::Tag(x))),
-            _ => Some(Node::Raw(self.read_to(&[b'<']))),
+        if ch != b'<' {
+            return Some(Node::Raw(self.read_to(&[b'<'])));
+        }
+
+        match self.classify_markup() {
+            MarkupKind::Comment => Some(Node::Comment(self.parse_comment())),
+            MarkupKind::Doctype => Some(Node::Doctype(self.parse_doctype())),
+            // A stray end tag here has no open tag to match in this single-pass
+            // parser; fall through to `parse_tag` as before. `read_ident` yields an
+            // empty name at the `/`, which `parse_tag` then takes as a self-closing
+            // marker, so `None` is returned unless a `>` directly follows it.
+            MarkupKind::EndTag | MarkupKind::StartTag => {
+                // TODO: if parse_tag fails (None case), we should probably just
+                // interpret it as raw text...
+                self.parse_tag(true).and_then(|x| Some(Node::Tag(x)))
+            }
+        }
+    }
+
+    /// Tokenizes the entire input into a flat stream of start-tag, end-tag, text, comment
+    /// and doctype tokens, without regard for tag nesting. [`build_tree`] turns this into a [`Tree`].
+    fn tokenize(&mut self) -> Vec<Token<'a>> {
+        let mut tokens = Vec::new();
+
+        while !self.stream.is_eof() {
+            self.skip_whitespaces();
+
+            let ch = match self.stream.current_cpy() {
+                Some(ch) => ch,
+                None => break,
+            };
+
+            if ch != b'<' {
+                tokens.push(Token::Text(self.read_to(&[b'<'])));
+                continue;
+            }
+
+            match self.classify_markup() {
+                MarkupKind::Comment => {
+                    tokens.push(Token::Comment(self.parse_comment()));
+                    continue;
+                }
+                MarkupKind::Doctype => {
+                    tokens.push(Token::Doctype(self.parse_doctype()));
+                    continue;
+                }
+                MarkupKind::EndTag => {
+                    self.stream.idx += END_OF_TAG.len();
+
+                    let name = match self.read_ident() {
+                        Some(name) => name,
+                        None => break,
+                    };
+
+                    self.skip_whitespaces();
+                    self.stream.expect_and_skip(b'>');
+
+                    tokens.push(Token::EndTag { name });
+                    continue;
+                }
+                MarkupKind::StartTag => {}
+            }
+
+            if self.stream.next().is_none() {
+                break;
+            }
+
+            let name = match self.read_ident() {
+                Some(name) => name,
+                None => break,
+            };
+
+            let attributes = self.parse_attributes();
+
+            let self_closing = self
+                .stream
+                .expect_and_skip(b'/')
+                .map(|c| c == b'/')
+                .unwrap_or(false);
+
+            self.skip_whitespaces();
+
+            if self.stream.expect_and_skip(b'>').is_none() {
+                break;
+            }
+
+            tokens.push(Token::StartTag {
+                name,
+                attributes,
+                self_closing,
+            });
+
+            if !self_closing && is_raw_text_node(name) {
+                let raw = self.read_raw_text(name);
+
+                if !raw.is_empty() {
+                    tokens.push(Token::Text(raw));
+                }
+
+                tokens.push(Token::EndTag { name });
+            }
         }
+
+        tokens
     }
 
     pub fn parse(&mut self) -> Tree<'a> {
-        let mut tree = Vec::new();
+        if self.options.strict {
+            let mut tree = Vec::new();
+
+            while let Some(node) = self.parse_single() {
+                tree.push(node);
+            }
+
+            tree
+        } else {
+            build_tree(self.tokenize())
+        }
+    }
+}
+
+/// Exports a parsed [`Tree`] as a Graphviz DOT graph, for visually inspecting how the
+/// parser nested a document.
+#[cfg(feature = "graphviz")]
+pub mod graphviz {
+    use super::{HTMLTag, Node, Tree};
+    use std::fmt;
 
+    const MAX_LABEL_LEN: usize = 40;
 
-        while let Some(node) = self.parse_single() {
-            tree.push(node);
+    /// Writes `tree` to `output` as a Graphviz `digraph`: one node per [`HTMLTag`] and
+    /// per `Raw`/`Comment`/`Doctype` node, with edges from each element to its children
+    /// in document order.
+    pub fn write_dot<W: fmt::Write>(tree: &Tree<'_>, output: &mut W) -> fmt::Result {
+        writeln!(output, "digraph tl {{")?;
+
+        let mut next_id = 0usize;
+        for node in tree {
+            write_node(node, None, &mut next_id, output)?;
+        }
+
+        writeln!(output, "}}")
+    }
+
+    fn write_node<W: fmt::Write>(
+        node: &Node<'_>,
+        parent: Option<usize>,
+        next_id: &mut usize,
+        output: &mut W,
+    ) -> fmt::Result {
+        let id = *next_id;
+        *next_id += 1;
+
+        let label = match node {
+            Node::Tag(tag) => describe_tag(tag),
+            Node::Raw(raw) => describe_text(raw),
+            Node::Comment(content) => format!("<!-- {} -->", describe_text(content)),
+            Node::Doctype(content) => format!("<!{}>", describe_text(content)),
+        };
+
+        writeln!(output, "  n{} [label=\"{}\"];", id, escape(&label))?;
+
+        if let Some(parent) = parent {
+            writeln!(output, "  n{} -> n{};", parent, id)?;
+        }
+
+        if let Node::Tag(tag) = node {
+            for child in &tag._children {
+                write_node(child, Some(id), next_id, output)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A compact, human-readable summary of a tag: its name plus a `key="value"` list
+    /// of its attributes.
+    fn describe_tag(tag: &HTMLTag<'_>) -> String {
+        let name = String::from_utf8_lossy(tag._name);
+
+        if tag._attributes.is_empty() {
+            return name.into_owned();
+        }
+
+        let mut attrs: Vec<String> = tag
+            ._attributes
+            .iter()
+            .map(|(k, v)| {
+                format!(
+                    "{}=\"{}\"",
+                    String::from_utf8_lossy(k),
+                    String::from_utf8_lossy(v)
+                )
+            })
+            .collect();
+        attrs.sort();
+
+        format!("{} {}", name, attrs.join(" "))
+    }
+
+    /// A trimmed, truncated snippet of text content, for use as a node label.
+    fn describe_text(bytes: &[u8]) -> String {
+        let text = String::from_utf8_lossy(bytes);
+        let trimmed = text.trim();
+
+        if trimmed.chars().count() > MAX_LABEL_LEN {
+            let truncated: String = trimmed.chars().take(MAX_LABEL_LEN).collect();
+            format!("{}...", truncated)
+        } else {
+            trimmed.to_string()
+        }
+    }
+
+    /// Escapes `"`, `\` and newlines so `label` is safe to embed in a DOT label.
+    fn escape(label: &str) -> String {
+        let mut escaped = String::with_capacity(label.len());
+
+        for ch in label.chars() {
+            match ch {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                _ => escaped.push(ch),
+            }
         }
 
-        tree
+        escaped
     }
 }
\ No newline at end of file
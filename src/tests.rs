@@ -277,6 +277,33 @@ mod simd {
         assert_eq!(util::find_fast_4(b"ef ghijklmnopqrstu", NEEDLE), None);
     }
 
+    #[test]
+    fn find_slice_fast() {
+        assert_eq!(util::find_slice_fast(b"", b"</script"), None);
+        assert_eq!(util::find_slice_fast(b"hello", b"</script"), None);
+        assert_eq!(util::find_slice_fast(b"</script>", b"</script"), Some(0));
+        assert_eq!(util::find_slice_fast(b"abc</script>", b"</script"), Some(3));
+        assert_eq!(util::find_slice_fast(b"-->", b"-->"), Some(0));
+        assert_eq!(util::find_slice_fast(b"a -- > -->", b"-->"), Some(7));
+
+        // partial match that never completes before EOF
+        assert_eq!(util::find_slice_fast(b"a </scrip", b"</script"), None);
+
+        // needle straddling a 16-byte SIMD register boundary
+        let haystack = format!("{}</script>", "a".repeat(14));
+        assert_eq!(
+            util::find_slice_fast(haystack.as_bytes(), b"</script"),
+            Some(14)
+        );
+
+        // needle starting exactly on a register boundary
+        let haystack = format!("{}</script>", "a".repeat(16));
+        assert_eq!(
+            util::find_slice_fast(haystack.as_bytes(), b"</script"),
+            Some(16)
+        );
+    }
+
     #[test]
     #[rustfmt::skip]
     fn search_non_ident() {
@@ -293,6 +320,40 @@ mod simd {
     }
 }
 
+#[cfg(feature = "graphviz")]
+mod graphviz {
+    use crate::parser::{graphviz::write_dot, Parser, ParserOptions};
+
+    #[test]
+    fn simple_tree() {
+        let mut parser = Parser::new("<div><p>hi</p></div>", ParserOptions::default());
+        let tree = parser.parse();
+
+        let mut out = String::new();
+        write_dot(&tree, &mut out).unwrap();
+
+        assert!(out.starts_with("digraph tl {\n"));
+        assert!(out.ends_with("}\n"));
+        assert!(out.contains("label=\"div\""));
+        assert!(out.contains("label=\"p\""));
+        assert!(out.contains("label=\"hi\""));
+        assert!(out.contains(" -> "));
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        let mut parser = Parser::new(r#"<p title="a \"quote\"">line1
+line2</p>"#, ParserOptions::default());
+        let tree = parser.parse();
+
+        let mut out = String::new();
+        write_dot(&tree, &mut out).unwrap();
+
+        assert!(!out.contains("line1\nline2\""));
+        assert!(out.contains("\\n"));
+    }
+}
+
 mod bytes {
     use crate::bytes::*;
 
@@ -398,6 +459,214 @@ fn valueless_attribute() {
     assert!(element.is_some());
 }
 
+#[test]
+fn detects_html5_doctype_node() {
+    let mut parser = Parser::new("<!DOCTYPE html> hello", ParserOptions::default());
+    let tree = parser.parse();
+
+    assert_eq!(doctype_is_html5(&tree), Some(true));
+}
+
+#[test]
+fn detects_non_html5_doctype_node() {
+    let mut parser = Parser::new("<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01//EN\"> hello", ParserOptions::default());
+    let tree = parser.parse();
+
+    assert_eq!(doctype_is_html5(&tree), Some(false));
+}
+
+#[test]
+fn detects_no_doctype() {
+    let mut parser = Parser::new("hello", ParserOptions::default());
+    let tree = parser.parse();
+
+    assert_eq!(doctype_is_html5(&tree), None);
+}
+
+#[test]
+fn parses_comment_node() {
+    let mut parser = Parser::new("<!-- hello -->", ParserOptions::default());
+    let tree = parser.parse();
+
+    assert!(matches!(tree[0], Node::Comment(b" hello ")));
+}
+
+#[test]
+fn parses_doctype_node() {
+    let mut parser = Parser::new("<!DOCTYPE html>", ParserOptions::default());
+    let tree = parser.parse();
+
+    assert!(matches!(tree[0], Node::Doctype(b"DOCTYPE html")));
+}
+
+#[test]
+fn comment_and_doctype_roundtrip() {
+    let input = "<!DOCTYPE html><!-- a comment --><p>hi</p>";
+    let mut parser = Parser::new(input, ParserOptions::default());
+    let tree = parser.parse();
+
+    let mut out = String::new();
+    for node in &tree {
+        node.outer_html(&mut out);
+    }
+
+    assert_eq!(out, input);
+}
+
+#[test]
+fn outer_html_roundtrip() {
+    let input = r#"<div class="a"><p>hi</p></div>"#;
+    let mut parser = Parser::new(input, ParserOptions::default());
+    let tree = parser.parse();
+
+    let mut out = String::new();
+    for node in &tree {
+        node.outer_html(&mut out);
+    }
+
+    assert_eq!(out, input);
+}
+
+#[test]
+fn outer_html_escapes_value_containing_both_quotes() {
+    let mut attributes = std::collections::HashMap::new();
+    attributes.insert(b"title" as &[u8], b"foo\"bar'baz" as &[u8]);
+    let tag = HTMLTag::new(b"p", attributes, Vec::new());
+
+    let mut out = String::new();
+    tag.outer_html(&mut out);
+
+    assert_eq!(out, r#"<p title="foo&quot;bar'baz"></p>"#);
+}
+
+#[test]
+fn outer_html_void_element() {
+    let mut parser = Parser::new(r#"<br>after"#, ParserOptions::default());
+    let tree = parser.parse();
+
+    let mut out = String::new();
+    for node in &tree {
+        node.outer_html(&mut out);
+    }
+
+    assert_eq!(out, "<br />after");
+}
+
+#[test]
+fn inner_html_children_only() {
+    let mut parser = Parser::new("<p>hello <b>world</b></p>", ParserOptions::default());
+    let tree = parser.parse();
+
+    let tag = force_as_tag(&tree[0]);
+
+    let mut out = String::new();
+    tag.inner_html(&mut out);
+
+    assert_eq!(out, "hello <b>world</b>");
+}
+
+#[test]
+fn implicit_li_close() {
+    // None of these <li>s are explicitly closed
+    let input = "<ul><li>one<li>two<li>three</ul>";
+    let dom = parse(input, ParserOptions::default()).unwrap();
+    let parser = dom.parser();
+
+    let ul = force_as_tag(dom.children()[0].get(parser).unwrap());
+
+    assert_eq!(ul.inner_text(parser), "onetwothree");
+}
+
+#[test]
+fn implicit_p_close() {
+    // The first <p> is closed by the following block-level <div>, not </p>
+    let input = "<p>hello<div>world</div>";
+    let dom = parse(input, ParserOptions::default()).unwrap();
+    let parser = dom.parser();
+
+    let p = force_as_tag(dom.children()[0].get(parser).unwrap());
+    let div = force_as_tag(dom.children()[1].get(parser).unwrap());
+
+    assert_eq!(p.inner_text(parser), "hello");
+    assert_eq!(div.inner_text(parser), "world");
+}
+
+#[test]
+fn implicit_p_close_through_non_closing_ancestor() {
+    // <b> doesn't appear in IMPLICIT_END_TAGS, but the scan must walk past it to find
+    // the <p> further down the stack that <div> does implicitly close.
+    let input = "<p>text<b>bold</b><div>after</div>";
+    let mut parser = Parser::new(input, ParserOptions::default());
+    let tree = parser.parse();
+
+    let mut out = String::new();
+    for node in &tree {
+        node.outer_html(&mut out);
+    }
+
+    assert_eq!(
+        out,
+        "<p>text<b>bold</b></p><div>after</div>"
+    );
+}
+
+#[test]
+fn implicit_table_row_close() {
+    // Neither <tr> is explicitly closed, and the second <tr> must close the first
+    // row's dangling <td> as well as the row itself, not nest inside it.
+    let input = "<table><tr><td>a<tr><td>b</table>";
+    let mut parser = Parser::new(input, ParserOptions::default());
+    let tree = parser.parse();
+
+    let mut out = String::new();
+    for node in &tree {
+        node.outer_html(&mut out);
+    }
+
+    assert_eq!(
+        out,
+        "<table><tr><td>a</td></tr><tr><td>b</td></tr></table>"
+    );
+}
+
+#[test]
+fn stray_end_tag_is_dropped() {
+    // </span> has no matching open element and should be silently ignored
+    let input = "<div>hello</span></div>";
+    let dom = parse(input, ParserOptions::default()).unwrap();
+    let parser = dom.parser();
+
+    let div = force_as_tag(dom.children()[0].get(parser).unwrap());
+
+    assert_eq!(div.inner_text(parser), "hello");
+}
+
+#[test]
+fn raw_text_script() {
+    let input = r#"<script>if (a < b) { console.log("hi"); }</script>"#;
+    let dom = parse(input, ParserOptions::default()).unwrap();
+    let parser = dom.parser();
+
+    let el = force_as_tag(dom.children()[0].get(parser).unwrap());
+
+    assert_eq!(
+        el.inner_text(parser),
+        r#"if (a < b) { console.log("hi"); }"#
+    );
+}
+
+#[test]
+fn raw_text_unterminated() {
+    // No closing </script> at all - should consume to EOF instead of failing
+    let input = r#"<script>var x = 1;"#;
+    let dom = parse(input, ParserOptions::default()).unwrap();
+    let parser = dom.parser();
+
+    let el = force_as_tag(dom.children()[0].get(parser).unwrap());
+
+    assert_eq!(el.inner_text(parser), "var x = 1;");
+}
+
 #[test]
 fn unquoted() {
     // https://github.com/y21/tl/issues/12
@@ -0,0 +1,90 @@
+/// Whether `ch` can appear in a tag name or attribute name (alphanumeric, `-` or `_`).
+pub fn is_ident(ch: u8) -> bool {
+    ch.is_ascii_alphanumeric() || ch == b'-' || ch == b'_'
+}
+
+/// Returns the starting index of the first occurrence of `needle` in `haystack`, or
+/// `None` if it isn't present.
+///
+/// Under the `simd` feature, this uses [`simd::find_slice_fast`] to search in near-
+/// memchr time by broadcasting the needle's first byte across a SIMD register to find
+/// candidate positions, verifying each with a scalar comparison of the full needle.
+/// Falls back to [`find_slice_scalar`] when the `simd` feature is disabled, or when
+/// `needle` is longer than the vector width.
+pub fn find_slice_fast(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    #[cfg(feature = "simd")]
+    {
+        if needle.len() <= simd::LANES {
+            return simd::find_slice_fast(haystack, needle);
+        }
+    }
+
+    find_slice_scalar(haystack, needle)
+}
+
+/// Naive byte-by-byte search for `needle` in `haystack`.
+fn find_slice_scalar(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+
+    haystack.windows(needle.len()).position(|window| window.eq(needle))
+}
+
+#[cfg(feature = "simd")]
+mod simd {
+    /// Width, in bytes, of the SIMD register used to scan for candidate positions.
+    pub const LANES: usize = 16;
+
+    #[cfg(target_arch = "x86_64")]
+    pub fn find_slice_fast(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        if is_x86_feature_detected!("sse2") {
+            unsafe { find_slice_fast_sse2(haystack, needle) }
+        } else {
+            super::find_slice_scalar(haystack, needle)
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    pub fn find_slice_fast(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        super::find_slice_scalar(haystack, needle)
+    }
+
+    /// Broadcasts `needle[0]` across an SSE2 register to find candidate positions in
+    /// 16-byte chunks of `haystack`, verifying each candidate with a scalar comparison
+    /// of the rest of `needle`. Any tail shorter than [`LANES`] is handled by
+    /// [`super::find_slice_scalar`].
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "sse2")]
+    unsafe fn find_slice_fast_sse2(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        use std::arch::x86_64::{_mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8};
+
+        let first = _mm_set1_epi8(needle[0] as i8);
+        let mut i = 0;
+
+        while i + LANES <= haystack.len() {
+            let chunk = _mm_loadu_si128(haystack.as_ptr().add(i) as *const _);
+            let eq = _mm_cmpeq_epi8(chunk, first);
+            let mut mask = _mm_movemask_epi8(eq) as u32;
+
+            while mask != 0 {
+                let bit = mask.trailing_zeros() as usize;
+                let candidate = i + bit;
+
+                if haystack[candidate..].starts_with(needle) {
+                    return Some(candidate);
+                }
+
+                mask &= mask - 1;
+            }
+
+            i += LANES;
+        }
+
+        super::find_slice_scalar(&haystack[i..], needle).map(|pos| pos + i)
+    }
+}